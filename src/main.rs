@@ -1,19 +1,26 @@
 use ggez::{Context, ContextBuilder, GameResult};
-use ggez::graphics::{self, Color, Text, DrawParam, Rect};
+use ggez::graphics::{self, Color, DrawParam, Image, Rect};
 use ggez::event::{self, EventHandler};
 use ggez::input::keyboard::{KeyCode, KeyInput};
 use ggez::conf;
 use glam::Vec2;
+use std::collections::HashMap;
 use std::time::Instant;
 use std::fs;
+use std::io::Read;
 use std::path::Path;
 use serde::{Serialize, Deserialize};
 
-const BLOCK_SIZE: f32 = 50.0;
-const GAME_WIDTH: i32 = 15;
-const PLATFORM_BLOCKS: i32 = 5;
-const MOVE_INTERVAL: f32 = 0.2;
-const SPEED_INCREASE: f32 = 0.92; // Decrease interval
+// Fixed-timestep simulation: keeps MOVE_INTERVAL/SPEED_INCREASE meaning the
+// same thing regardless of the display's refresh rate.
+const TICK: f32 = 1.0 / 60.0;
+// Cap how much sim time a single real frame can owe, so a stall (e.g. a
+// stutter while resizing) doesn't trigger a spiral of death of catch-up ticks.
+const MAX_ACCUMULATOR: f32 = TICK * 5.0;
+
+// Exponential decay rate for the camera easing toward its target each
+// frame; higher catches up faster.
+const CAMERA_SMOOTHING: f32 = 8.0;
 
 #[derive(Clone)]
 struct GridBlock {
@@ -24,14 +31,6 @@ struct GridBlock {
     fall_offset: f32,
 }
 
-#[derive(PartialEq)]
-enum GameState {
-    Menu,
-    Playing,
-    GameOver(Instant),  // Add timestamp for game over state
-    Settings,
-}
-
 #[derive(Serialize, Deserialize)]
 struct GameStats {
     high_score: i32,
@@ -45,9 +44,24 @@ impl GameStats {
             games_played: 0,
         }
     }
+}
 
-    fn load() -> Self {
-        let path = Path::new("game_stats.json");
+/// Where `GameStats` is persisted. Abstracted behind a trait because
+/// `std::fs` doesn't exist on the web: native builds read and write a JSON
+/// file next to the executable, while wasm32 builds persist the same JSON
+/// into `localStorage` instead.
+trait StatsStore {
+    fn load(&self) -> GameStats;
+    fn save(&self, stats: &GameStats);
+}
+
+const STATS_FILE: &str = "game_stats.json";
+
+struct FileStore;
+
+impl StatsStore for FileStore {
+    fn load(&self) -> GameStats {
+        let path = Path::new(STATS_FILE);
         if path.exists() {
             if let Ok(contents) = fs::read_to_string(path) {
                 if let Ok(stats) = serde_json::from_str(&contents) {
@@ -55,18 +69,449 @@ impl GameStats {
                 }
             }
         }
-        Self::new()
+        GameStats::new()
+    }
+
+    fn save(&self, stats: &GameStats) {
+        match serde_json::to_string_pretty(stats) {
+            Ok(json) => {
+                if let Err(e) = fs::write(STATS_FILE, json) {
+                    println!("Failed to save game stats: {}", e);
+                }
+            }
+            Err(e) => println!("Failed to serialize game stats: {}", e),
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+struct LocalStorageStore;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorageStore {
+    fn local_storage(&self) -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl StatsStore for LocalStorageStore {
+    fn load(&self) -> GameStats {
+        self.local_storage()
+            .and_then(|storage| storage.get_item(STATS_FILE).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(GameStats::new)
+    }
+
+    fn save(&self, stats: &GameStats) {
+        let Some(storage) = self.local_storage() else {
+            println!("Failed to save game stats: localStorage is unavailable");
+            return;
+        };
+        match serde_json::to_string(stats) {
+            Ok(json) => {
+                if let Err(e) = storage.set_item(STATS_FILE, &json) {
+                    println!("Failed to save game stats: {:?}", e);
+                }
+            }
+            Err(e) => println!("Failed to serialize game stats: {}", e),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn make_stats_store() -> Box<dyn StatsStore> {
+    Box::new(FileStore)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn make_stats_store() -> Box<dyn StatsStore> {
+    Box::new(LocalStorageStore)
+}
+
+const FONT_ATLAS_PATH: &str = "/font_atlas.png";
+const FONT_GLYPH_MAP_PATH: &str = "/font_atlas.json5";
+
+/// The glyph-rectangle layout of a `BitmapFont`'s atlas, loaded from a
+/// JSON5 sidecar next to the PNG. Rectangles are in atlas pixels; `glyphs`
+/// maps each supported character (as a one-character string, since JSON
+/// object keys are strings) to its source rect.
+#[derive(Deserialize)]
+struct GlyphMap {
+    glyph_width: f32,
+    glyph_height: f32,
+    glyphs: HashMap<String, [f32; 4]>,
+}
+
+/// Renders text by blitting per-character regions out of a monospaced
+/// glyph atlas instead of going through ggez's default `Text`, so the HUD
+/// has its own pixel-font identity and scales without going blurry.
+struct BitmapFont {
+    atlas: Image,
+    glyph_width: f32,
+    glyph_height: f32,
+    glyphs: HashMap<String, Rect>,
+}
+
+impl BitmapFont {
+    fn load(ctx: &Context) -> GameResult<Self> {
+        let atlas = Image::from_path(ctx, FONT_ATLAS_PATH)?;
+        let (atlas_width, atlas_height) = (atlas.width() as f32, atlas.height() as f32);
+
+        // Read the sidecar through ggez's virtual filesystem rather than
+        // `std::fs`, so it resolves against the same `resources/` root as
+        // the atlas PNG above instead of the process's current directory.
+        let mut file = ctx.fs.open(FONT_GLYPH_MAP_PATH)?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))?;
+        let map: GlyphMap = json5::from_str(&contents)
+            .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))?;
+
+        let glyphs = map
+            .glyphs
+            .into_iter()
+            .map(|(glyph, [x, y, w, h])| {
+                // DrawParam::src wants UV coordinates, not atlas pixels.
+                let rect = Rect::new(x / atlas_width, y / atlas_height, w / atlas_width, h / atlas_height);
+                (glyph, rect)
+            })
+            .collect();
+
+        Ok(Self {
+            atlas,
+            glyph_width: map.glyph_width,
+            glyph_height: map.glyph_height,
+            glyphs,
+        })
+    }
+
+    /// A font that draws nothing. Used when the atlas assets fail to load
+    /// so a missing/corrupt `resources/` directory degrades the HUD to
+    /// blank text instead of crashing the game on startup.
+    fn noop(ctx: &Context) -> Self {
+        Self {
+            atlas: Image::from_color(ctx, 1, 1, Some(Color::WHITE)),
+            glyph_width: 0.0,
+            glyph_height: 0.0,
+            glyphs: HashMap::new(),
+        }
+    }
+
+    fn draw_text(&self, canvas: &mut graphics::Canvas, text: &str, pos: Vec2, scale: f32, color: Color) {
+        let mut cursor = pos;
+        for ch in text.chars() {
+            if ch == '\n' {
+                cursor.x = pos.x;
+                cursor.y += self.glyph_height * scale;
+                continue;
+            }
+            if let Some(src) = self.glyphs.get(&ch.to_string()) {
+                canvas.draw(
+                    &self.atlas,
+                    DrawParam::default()
+                        .src(*src)
+                        .dest(cursor)
+                        .scale(Vec2::new(scale, scale))
+                        .color(color),
+                );
+            }
+            cursor.x += self.glyph_width * scale;
+        }
+    }
+}
+
+/// Tunable gameplay parameters, loaded from `config.json5` next to the
+/// executable so difficulty can be tweaked without a rebuild. JSON5 (rather
+/// than plain JSON) lets players keep comments and trailing commas in their
+/// presets. Falls back to the shipped defaults if the file is missing or
+/// fails to parse.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct GameConfig {
+    grid_width: i32,
+    platform_width: i32,
+    move_interval: f32,
+    speed_increase: f32,
+    block_size: f32,
+    platform_color_even: [f32; 4],
+    platform_color_odd: [f32; 4],
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            grid_width: 15,
+            platform_width: 5,
+            move_interval: 0.2,
+            speed_increase: 0.92, // Decrease interval
+            block_size: 50.0,
+            platform_color_even: [0.0, 0.0, 1.0, 1.0], // Blue
+            platform_color_odd: [1.0, 1.0, 0.0, 1.0],  // Yellow
+        }
+    }
+}
+
+impl GameConfig {
+    fn load() -> Self {
+        let path = Path::new("config.json5");
+        if path.exists() {
+            if let Ok(contents) = fs::read_to_string(path) {
+                if let Ok(config) = json5::from_str::<Self>(&contents) {
+                    if config.is_valid() {
+                        return config;
+                    }
+                }
+            }
+        }
+        Self::default()
     }
 
-    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let json = serde_json::to_string_pretty(self)?;
-        fs::write("game_stats.json", json)?;
+    /// Rejects values a hand-edited `config.json5` could plausibly contain
+    /// but that the grid/platform math below can't tolerate: a non-positive
+    /// `grid_width` would cast to a huge `usize` when sizing a row's `Vec`,
+    /// and a `platform_width` wider than the grid would place platforms off
+    /// the edge of it.
+    fn is_valid(&self) -> bool {
+        self.grid_width > 0
+            && self.platform_width > 0
+            && self.platform_width <= self.grid_width
+            && self.block_size > 0.0
+            && self.move_interval > 0.0
+            && self.speed_increase > 0.0
+    }
+}
+
+/// State shared across every scene: persistent stats, the current window
+/// size, and anything else a scene needs to read or update regardless of
+/// which screen is on top.
+struct SharedState {
+    stats: GameStats,
+    stats_store: Box<dyn StatsStore>,
+    config: GameConfig,
+    font: BitmapFont,
+    window_width: f32,
+    window_height: f32,
+    last_level: i32,
+}
+
+/// What a scene asks the driver to do to the scene stack after it runs.
+enum SceneTransition {
+    /// Push a new scene on top, leaving this one paused underneath.
+    Push(Box<dyn Scene>),
+    /// Pop this scene, revealing whatever is beneath it.
+    Pop,
+    /// Replace the entire stack with a new scene (a full screen switch).
+    Replace(Box<dyn Scene>),
+}
+
+/// A single screen in the game (menu, gameplay, settings, game over). Only
+/// the top of the stack is updated and fed input; `draw` runs for every
+/// scene in the stack so a scene can overlay the one beneath it.
+trait Scene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<Option<SceneTransition>>;
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut graphics::Canvas, shared: &SharedState) -> GameResult;
+    fn key_down(&mut self, ctx: &mut Context, input: KeyInput, shared: &mut SharedState) -> GameResult<Option<SceneTransition>>;
+
+    // Most scenes don't care about gamepad input, so default to a no-op
+    // like ggez's own EventHandler does for its optional event hooks.
+    fn gamepad_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        _button: event::Button,
+        _shared: &mut SharedState,
+    ) -> GameResult<Option<SceneTransition>> {
+        Ok(None)
+    }
+
+    // As above, but for analog stick movement (e.g. menu navigation on a
+    // pad whose D-pad is reported as a hat switch rather than buttons).
+    fn gamepad_axis(
+        &mut self,
+        _ctx: &mut Context,
+        _axis: event::Axis,
+        _value: f32,
+        _shared: &mut SharedState,
+    ) -> GameResult<Option<SceneTransition>> {
+        Ok(None)
+    }
+}
+
+const MENU_ITEM_COUNT: usize = 3;
+
+// How far a stick has to travel off-center before it counts as a nav input.
+const STICK_DEADZONE: f32 = 0.5;
+
+struct MenuScene {
+    selected: usize,
+    // Edge-triggers stick navigation: only armed once the stick has
+    // returned to its deadzone, so holding it past the threshold doesn't
+    // spam the selection every frame like the DPad buttons never would.
+    stick_armed: bool,
+}
+
+impl MenuScene {
+    fn new() -> Self {
+        Self {
+            selected: 0,
+            stick_armed: true,
+        }
+    }
+
+    fn activate(&self, shared: &mut SharedState) -> Option<SceneTransition> {
+        match self.selected {
+            0 => Some(SceneTransition::Replace(Box::new(PlayScene::new(shared)))),
+            1 => Some(SceneTransition::Push(Box::new(SettingsScene::new()))),
+            2 => std::process::exit(0),
+            _ => None,
+        }
+    }
+}
+
+impl Scene for MenuScene {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        Ok(None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut graphics::Canvas, shared: &SharedState) -> GameResult {
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            0.0,
+            shared.window_width,
+            shared.window_height,
+        ));
+
+        let menu_text = vec![
+            ("Start", shared.window_height / 2.0 - 60.0),
+            ("Settings", shared.window_height / 2.0),
+            ("Quit", shared.window_height / 2.0 + 60.0),
+        ];
+
+        for (i, (text, y)) in menu_text.into_iter().enumerate() {
+            let pos = Vec2::new(shared.window_width / 2.0 - 50.0, y);
+            // Highlight whichever item a gamepad cursor would activate.
+            let color = if i == self.selected { Color::YELLOW } else { Color::WHITE };
+            shared.font.draw_text(canvas, text, pos, 1.0, color);
+        }
+
+        // Draw stats
+        let stats_text = vec![
+            format!("High Score: {}", shared.stats.high_score),
+            format!("Games Played: {}", shared.stats.games_played),
+        ];
+
+        for (i, text) in stats_text.iter().enumerate() {
+            let pos = Vec2::new(
+                shared.window_width / 2.0 - 50.0,
+                shared.window_height / 2.0 + 120.0 + i as f32 * 30.0
+            );
+            shared.font.draw_text(canvas, text, pos, 1.0, Color::WHITE);
+        }
+
+        // Draw last level if it exists
+        if shared.last_level > 0 {
+            let pos = Vec2::new(shared.window_width / 2.0 - 50.0, shared.window_height / 2.0 + 180.0);
+            shared.font.draw_text(canvas, &format!("Last Level: {}", shared.last_level), pos, 1.0, Color::WHITE);
+        }
+
         Ok(())
     }
+
+    fn key_down(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        match input.keycode {
+            Some(KeyCode::Return) => Ok(Some(SceneTransition::Replace(Box::new(PlayScene::new(shared))))),
+            Some(KeyCode::S) => Ok(Some(SceneTransition::Push(Box::new(SettingsScene::new())))),
+            Some(KeyCode::Q) => std::process::exit(0),
+            _ => Ok(None),
+        }
+    }
+
+    fn gamepad_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        button: event::Button,
+        shared: &mut SharedState,
+    ) -> GameResult<Option<SceneTransition>> {
+        match button {
+            event::Button::DPadUp => {
+                self.selected = (self.selected + MENU_ITEM_COUNT - 1) % MENU_ITEM_COUNT;
+                Ok(None)
+            }
+            event::Button::DPadDown => {
+                self.selected = (self.selected + 1) % MENU_ITEM_COUNT;
+                Ok(None)
+            }
+            event::Button::South => Ok(self.activate(shared)),
+            // Start always jumps straight into a game, mirroring Return.
+            event::Button::Start => Ok(Some(SceneTransition::Replace(Box::new(PlayScene::new(shared))))),
+            _ => Ok(None),
+        }
+    }
+
+    fn gamepad_axis(
+        &mut self,
+        _ctx: &mut Context,
+        axis: event::Axis,
+        value: f32,
+        _shared: &mut SharedState,
+    ) -> GameResult<Option<SceneTransition>> {
+        if axis != event::Axis::LeftStickY {
+            return Ok(None);
+        }
+        if value.abs() < STICK_DEADZONE {
+            self.stick_armed = true;
+            return Ok(None);
+        }
+        if !self.stick_armed {
+            return Ok(None);
+        }
+        self.stick_armed = false;
+        if value > 0.0 {
+            self.selected = (self.selected + MENU_ITEM_COUNT - 1) % MENU_ITEM_COUNT;
+        } else {
+            self.selected = (self.selected + 1) % MENU_ITEM_COUNT;
+        }
+        Ok(None)
+    }
 }
 
-struct GameData {
-    state: GameState,
+struct SettingsScene;
+
+impl SettingsScene {
+    fn new() -> Self {
+        Self
+    }
+}
+
+impl Scene for SettingsScene {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        Ok(None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut graphics::Canvas, shared: &SharedState) -> GameResult {
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            0.0,
+            shared.window_width,
+            shared.window_height,
+        ));
+
+        let pos = Vec2::new(shared.window_width / 2.0 - 100.0, shared.window_height / 2.0);
+        shared.font.draw_text(canvas, "Settings (Press Esc to return)", pos, 1.0, Color::WHITE);
+
+        Ok(())
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, input: KeyInput, _shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        if let Some(KeyCode::Escape) = input.keycode {
+            return Ok(Some(SceneTransition::Pop));
+        }
+        Ok(None)
+    }
+}
+
+struct PlayScene {
+    config: GameConfig,
     grid: Vec<Vec<GridBlock>>,
     platform_position: i32,
     platform_width: i32,
@@ -75,60 +520,68 @@ struct GameData {
     move_interval: f32,
     level: i32,
     camera_offset_y: f32,
-    window_width: f32,
-    window_height: f32,
+    camera_target_y: f32,
     current_row: i32,
     moving_platform_pos: i32,
-    stats: GameStats,
+    accumulator: f32,
+    // Set once the platform misses and the high score has been recorded;
+    // the scene keeps drawing its frozen board beneath the game-over
+    // overlay until it is put back on top of the stack, at which point it
+    // hands off to the menu instead of simulating any further.
+    game_over: bool,
 }
 
-impl GameData {
-    fn new(ctx: &Context) -> Self {
-        let window_width = ctx.gfx.window().inner_size().width as f32;
-        let window_height = ctx.gfx.window().inner_size().height as f32;
-        let platform_pos = (GAME_WIDTH - PLATFORM_BLOCKS) / 2;
-        
-        Self {
-            state: GameState::Menu,
+impl PlayScene {
+    fn new(shared: &mut SharedState) -> Self {
+        shared.stats.games_played += 1;
+        shared.stats_store.save(&shared.stats);
+
+        let config = shared.config.clone();
+        let platform_pos = (config.grid_width - config.platform_width) / 2;
+        let mut scene = Self {
+            move_interval: config.move_interval,
+            platform_width: config.platform_width,
+            config,
             grid: Vec::new(),
             platform_position: platform_pos,
-            platform_width: PLATFORM_BLOCKS,
             move_right: true,
             move_timer: 0.0,
-            move_interval: MOVE_INTERVAL,
             level: 0,
             camera_offset_y: 0.0,
-            window_width,
-            window_height,
+            camera_target_y: 0.0,
             current_row: 0,
             moving_platform_pos: platform_pos,
-            stats: GameStats::load(),
-        }
+            accumulator: 0.0,
+            game_over: false,
+        };
+        scene.reset_game();
+        scene
     }
 
     fn reset_game(&mut self) {
         self.grid.clear();
-        self.platform_width = PLATFORM_BLOCKS;
-        self.platform_position = (GAME_WIDTH - self.platform_width) / 2;
+        self.platform_width = self.config.platform_width;
+        self.platform_position = (self.config.grid_width - self.platform_width) / 2;
         self.moving_platform_pos = self.platform_position;
         self.current_row = 0;
-        self.move_interval = MOVE_INTERVAL;
+        self.move_interval = self.config.move_interval;
+        self.accumulator = 0.0;
         self.add_base_row();
         self.add_new_row();
     }
 
     fn add_base_row(&mut self) {
-        let mut row = vec![GridBlock { 
-            active: false, 
-            landed: false, 
+        let mut row = vec![GridBlock {
+            active: false,
+            landed: false,
             level: 0,
             falling: false,
             fall_offset: 0.0,
-        }; GAME_WIDTH as usize];
-        
+        }; self.config.grid_width as usize];
+
         for i in 0..self.platform_width {
             let pos = (self.platform_position + i) as usize;
-            if pos < GAME_WIDTH as usize {
+            if pos < self.config.grid_width as usize {
                 row[pos].active = true;
                 row[pos].landed = true;
                 row[pos].level = 0;
@@ -138,33 +591,22 @@ impl GameData {
     }
 
     fn add_new_row(&mut self) {
-        let mut row = vec![GridBlock { 
-            active: false, 
-            landed: false, 
+        let mut row = vec![GridBlock {
+            active: false,
+            landed: false,
             level: self.level,
             falling: false,
             fall_offset: 0.0,
-        }; GAME_WIDTH as usize];
-        
+        }; self.config.grid_width as usize];
+
         for i in 0..self.platform_width {
             let pos = (self.moving_platform_pos + i) as usize;
-            if pos < GAME_WIDTH as usize {
+            if pos < self.config.grid_width as usize {
                 row[pos].active = true;
             }
         }
-        
-        self.grid.push(row);
-    }
 
-    fn start_game(&mut self) {
-        self.state = GameState::Playing;
-        self.level = 0;
-        self.camera_offset_y = 0.0;
-        self.stats.games_played += 1;
-        if let Err(e) = self.stats.save() {
-            println!("Failed to save game stats: {}", e);
-        }
-        self.reset_game();
+        self.grid.push(row);
     }
 
     fn update_movement(&mut self, dt: f32) -> bool {
@@ -180,9 +622,9 @@ impl GameData {
         self.move_timer += dt;
         if self.move_timer >= self.move_interval {
             self.move_timer = 0.0;
-            
+
             if self.move_right {
-                if self.moving_platform_pos + self.platform_width < GAME_WIDTH {
+                if self.moving_platform_pos + self.platform_width < self.config.grid_width {
                     // Turn off leftmost block and turn on new rightmost block
                     if let Some(row) = self.grid.last_mut() {
                         // First ensure the previous block is off
@@ -191,7 +633,7 @@ impl GameData {
                         self.moving_platform_pos += 1;
                         // Finally activate new block
                         let new_pos = (self.moving_platform_pos + self.platform_width - 1) as usize;
-                        if new_pos < GAME_WIDTH as usize {
+                        if new_pos < self.config.grid_width as usize {
                             row[new_pos].active = true;
                         }
                     }
@@ -204,7 +646,7 @@ impl GameData {
                     if let Some(row) = self.grid.last_mut() {
                         // First ensure the previous block is off
                         let old_pos = (self.moving_platform_pos + self.platform_width - 1) as usize;
-                        if old_pos < GAME_WIDTH as usize {
+                        if old_pos < self.config.grid_width as usize {
                             row[old_pos].active = false;
                         }
                         // Then move position
@@ -226,9 +668,9 @@ impl GameData {
             let mut active_count = 0;
             let platform_start = self.platform_position;
             let platform_end = self.platform_position + self.platform_width - 1;
-            
+
             // First, mark blocks that should fall
-            for i in 0..GAME_WIDTH as usize {
+            for i in 0..self.config.grid_width as usize {
                 if current_row[i].active && !current_row[i].landed {
                     if i < platform_start as usize || i > platform_end as usize {
                         current_row[i].falling = true;
@@ -239,7 +681,7 @@ impl GameData {
 
             // Then count remaining active blocks and mark them as landed
             for i in platform_start..=platform_end {
-                if i >= 0 && i < GAME_WIDTH && current_row[i as usize].active {
+                if i >= 0 && i < self.config.grid_width && current_row[i as usize].active {
                     current_row[i as usize].landed = true;
                     active_count += 1;
                 }
@@ -248,7 +690,7 @@ impl GameData {
             // Update platform width based on successful landing
             if active_count > 0 {
                 self.platform_width = active_count;
-                self.platform_position = (GAME_WIDTH - self.platform_width) / 2;
+                self.platform_position = (self.config.grid_width - self.platform_width) / 2;
                 self.moving_platform_pos = self.platform_position;
                 self.current_row += 1;
                 return true;
@@ -257,266 +699,364 @@ impl GameData {
         false
     }
 
-    fn handle_window_resize(&mut self, ctx: &Context) {
-        self.window_width = ctx.gfx.window().inner_size().width as f32;
-        self.window_height = ctx.gfx.window().inner_size().height as f32;
+    fn get_platform_color(&self, level: i32) -> Color {
+        let [r, g, b, a] = if level % 2 == 0 {
+            self.config.platform_color_even
+        } else {
+            self.config.platform_color_odd
+        };
+        Color::new(r, g, b, a)
     }
 
-    fn get_platform_color(&self, level: i32) -> Color {
-        if level % 2 == 0 {
-            Color::new(0.0, 0.0, 1.0, 1.0)  // Blue
+    // Shared by the Space key and the gamepad's South button: land the
+    // moving platform, or end the run if it missed.
+    fn drop_platform(&mut self, shared: &mut SharedState) -> Option<SceneTransition> {
+        if self.check_landing() {
+            self.add_new_row();
+            self.level += 1;
+            self.move_interval *= self.config.speed_increase;
+            None
         } else {
-            Color::new(1.0, 1.0, 0.0, 1.0)  // Yellow
+            // Update high score when game ends
+            if self.level > shared.stats.high_score {
+                shared.stats.high_score = self.level;
+                shared.stats_store.save(&shared.stats);
+            }
+            self.game_over = true;
+            Some(SceneTransition::Push(Box::new(GameOverScene::new())))
         }
     }
 }
 
-impl EventHandler for GameData {
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        match self.state {
-            GameState::Playing => {
-                self.update_movement(ctx.time.delta().as_secs_f32());
-            }
-            GameState::GameOver(start_time) => {
-                if start_time.elapsed().as_secs() >= 3 {
-                    self.state = GameState::Menu;
-                }
-            }
-            _ => {}
+impl Scene for PlayScene {
+    fn update(&mut self, ctx: &mut Context, shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        if self.game_over {
+            // We only get the top of the stack back once the game-over
+            // overlay has popped itself; hand straight off to the menu.
+            shared.last_level = self.level;
+            return Ok(Some(SceneTransition::Replace(Box::new(MenuScene::new()))));
         }
-        Ok(())
+
+        self.accumulator += ctx.time.delta().as_secs_f32();
+        self.accumulator = self.accumulator.min(MAX_ACCUMULATOR);
+
+        while self.accumulator >= TICK {
+            self.accumulator -= TICK;
+            self.update_movement(TICK);
+        }
+
+        Ok(None)
     }
 
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        let mut canvas = graphics::Canvas::from_frame(ctx, Color::BLACK);
-        let block_draw_size = self.window_width / GAME_WIDTH as f32;
-        
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut graphics::Canvas, shared: &SharedState) -> GameResult {
+        let block_draw_size = self.config.block_size;
+
         // Calculate the position of the moving platform (last row)
-        if (self.state == GameState::Playing || matches!(self.state, GameState::GameOver(_))) 
-            && self.grid.len() > 1 && self.level >= 4 {
-            let moving_platform_y = self.window_height - ((self.grid.len()) as f32 * block_draw_size);
-            if moving_platform_y < self.window_height * 0.7 {
-                self.camera_offset_y = moving_platform_y - self.window_height * 0.3;
+        if self.grid.len() > 1 && self.level >= 4 {
+            let moving_platform_y = shared.window_height - ((self.grid.len()) as f32 * block_draw_size);
+            if moving_platform_y < shared.window_height * 0.7 {
+                self.camera_target_y = moving_platform_y - shared.window_height * 0.3;
             }
         }
 
+        // Ease toward the target rather than snapping to it, so the view
+        // doesn't visibly jump as the stack grows past level 4.
+        let dt = ctx.time.delta().as_secs_f32();
+        self.camera_offset_y += (self.camera_target_y - self.camera_offset_y) * (1.0 - (-CAMERA_SMOOTHING * dt).exp());
+        // Never let the scrolling content pan down past its resting
+        // position (the base platform itself is drawn in a separate,
+        // fixed screen-coordinate pass below and never uses this offset).
+        self.camera_offset_y = self.camera_offset_y.min(0.0);
+
         // Set up two coordinate systems: one for the fixed base and one for the scrolling content
-        let grid_start_x = (self.window_width - (GAME_WIDTH as f32 * block_draw_size)) / 2.0;
-
-        match self.state {
-            GameState::Menu => {
-                canvas.set_screen_coordinates(Rect::new(
-                    0.0,
-                    0.0,
-                    self.window_width,
-                    self.window_height,
-                ));
-
-                let menu_text = vec![
-                    ("Start", self.window_height / 2.0 - 60.0),
-                    ("Settings", self.window_height / 2.0),
-                    ("Quit", self.window_height / 2.0 + 60.0),
-                ];
-
-                for (text, y) in menu_text {
-                    let text = Text::new(text);
-                    let pos = Vec2::new(self.window_width / 2.0 - 50.0, y);
-                    canvas.draw(&text, DrawParam::default().dest(pos).color(Color::WHITE));
-                }
+        let grid_start_x = (shared.window_width - (self.config.grid_width as f32 * block_draw_size)) / 2.0;
 
-                // Draw stats
-                let stats_text = vec![
-                    format!("High Score: {}", self.stats.high_score),
-                    format!("Games Played: {}", self.stats.games_played),
-                ];
-
-                for (i, text) in stats_text.iter().enumerate() {
-                    let text = Text::new(text);
-                    let pos = Vec2::new(
-                        self.window_width / 2.0 - 50.0,
-                        self.window_height / 2.0 + 120.0 + i as f32 * 30.0
-                    );
-                    canvas.draw(&text, DrawParam::default().dest(pos).color(Color::WHITE));
-                }
+        // First draw the fixed base platform with no camera offset
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            0.0,
+            shared.window_width,
+            shared.window_height,
+        ));
+
+        if !self.grid.is_empty() {
+            let y = shared.window_height - block_draw_size;
+            canvas.draw(
+                &graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    Rect::new(
+                        grid_start_x + (self.platform_position as f32 * block_draw_size),
+                        y,
+                        block_draw_size * self.platform_width as f32,
+                        block_draw_size,
+                    ),
+                    self.get_platform_color(0),
+                )?,
+                DrawParam::default(),
+            );
+        }
+
+        // Then draw the moving blocks with camera offset
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            self.camera_offset_y,
+            shared.window_width,
+            shared.window_height,
+        ));
+
+        // Draw all rows except the base
+        for (row_idx, row) in self.grid.iter().enumerate().skip(1) {
+            let y = shared.window_height - ((row_idx + 1) as f32 * block_draw_size);
+
+            // Draw blocks
+            for (col_idx, block) in row.iter().enumerate() {
+                if block.active || block.falling {
+                    let mut color = if block.landed {
+                        self.get_platform_color(block.level)
+                    } else if block.falling {
+                        Color::RED
+                    } else {
+                        Color::GREEN
+                    };
+
+                    // Make falling blocks fade out
+                    if block.falling {
+                        color.a = (1.0 - (block.fall_offset / (shared.window_height / 2.0))).max(0.0);
+                    }
+
+                    let block_y = if block.falling {
+                        y + block.fall_offset
+                    } else {
+                        y
+                    };
 
-                // Draw last level if it exists
-                if self.level > 0 {
-                    let level_text = Text::new(format!("Last Level: {}", self.level));
-                    canvas.draw(
-                        &level_text,
-                        DrawParam::default()
-                            .dest(Vec2::new(self.window_width / 2.0 - 50.0, self.window_height / 2.0 + 180.0))
-                            .color(Color::WHITE),
-                    );
-                }
-            }
-            GameState::Playing | GameState::GameOver(_) => {
-                // First draw the fixed base platform with no camera offset
-                canvas.set_screen_coordinates(Rect::new(
-                    0.0,
-                    0.0,
-                    self.window_width,
-                    self.window_height,
-                ));
-
-                if let Some(base_row) = self.grid.first() {
-                    let y = self.window_height - block_draw_size;
                     canvas.draw(
                         &graphics::Mesh::new_rectangle(
                             ctx,
                             graphics::DrawMode::fill(),
                             Rect::new(
-                                grid_start_x + (self.platform_position as f32 * block_draw_size),
-                                y,
-                                block_draw_size * self.platform_width as f32,
+                                grid_start_x + (col_idx as f32 * block_draw_size),
+                                block_y,
+                                block_draw_size,
                                 block_draw_size,
                             ),
-                            self.get_platform_color(0),
+                            color,
                         )?,
                         DrawParam::default(),
                     );
                 }
+            }
+        }
 
-                // Then draw the moving blocks with camera offset
-                canvas.set_screen_coordinates(Rect::new(
-                    0.0,
-                    self.camera_offset_y,
-                    self.window_width,
-                    self.window_height,
-                ));
-
-                // Draw all rows except the base
-                for (row_idx, row) in self.grid.iter().enumerate().skip(1) {
-                    let y = self.window_height - ((row_idx + 1) as f32 * block_draw_size);
-                    
-                    // Draw blocks
-                    for (col_idx, block) in row.iter().enumerate() {
-                        if block.active || block.falling {
-                            let mut color = if block.landed { 
-                                self.get_platform_color(block.level)
-                            } else if block.falling {
-                                Color::RED
-                            } else { 
-                                Color::GREEN 
-                            };
-
-                            // Make falling blocks fade out
-                            if block.falling {
-                                color.a = (1.0 - (block.fall_offset / (self.window_height / 2.0))).max(0.0);
-                            }
-
-                            let block_y = if block.falling {
-                                y + block.fall_offset
-                            } else {
-                                y
-                            };
-
-                            canvas.draw(
-                                &graphics::Mesh::new_rectangle(
-                                    ctx,
-                                    graphics::DrawMode::fill(),
-                                    Rect::new(
-                                        grid_start_x + (col_idx as f32 * block_draw_size),
-                                        block_y,
-                                        block_draw_size,
-                                        block_draw_size,
-                                    ),
-                                    color,
-                                )?,
-                                DrawParam::default(),
-                            );
-                        }
-                    }
-                }
+        // Draw level text with fixed position relative to view
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            0.0,
+            shared.window_width,
+            shared.window_height,
+        ));
 
-                // Draw level text with fixed position relative to view
-                canvas.set_screen_coordinates(Rect::new(
-                    0.0,
-                    0.0,
-                    self.window_width,
-                    self.window_height,
-                ));
-                
-                // Draw large level counter in the center top of the screen
-                let level_text = Text::new(format!("Level {}", self.level));
-                let text_scale = 2.0;
-                canvas.draw(
-                    &level_text,
-                    DrawParam::default()
-                        .dest(Vec2::new(self.window_width / 2.0 - 50.0, 30.0))
-                        .scale(Vec2::new(text_scale, text_scale))
-                        .color(Color::WHITE),
-                );
+        // Draw large level counter in the center top of the screen
+        shared.font.draw_text(
+            canvas,
+            &format!("Level {}", self.level),
+            Vec2::new(shared.window_width / 2.0 - 50.0, 30.0),
+            2.0,
+            Color::WHITE,
+        );
 
-                // If in game over state, draw "Game Over" text
-                if matches!(self.state, GameState::GameOver(_)) {
-                    let game_over_text = Text::new("Game Over!");
-                    let text_scale = 3.0;
-                    canvas.draw(
-                        &game_over_text,
-                        DrawParam::default()
-                            .dest(Vec2::new(self.window_width / 2.0 - 100.0, self.window_height / 2.0))
-                            .scale(Vec2::new(text_scale, text_scale))
-                            .color(Color::RED),
-                    );
-                }
+        Ok(())
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, input: KeyInput, shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        if self.game_over {
+            return Ok(None);
+        }
+
+        if let Some(KeyCode::Space) = input.keycode {
+            return Ok(self.drop_platform(shared));
+        }
+        Ok(None)
+    }
+
+    fn gamepad_button_down(
+        &mut self,
+        _ctx: &mut Context,
+        button: event::Button,
+        shared: &mut SharedState,
+    ) -> GameResult<Option<SceneTransition>> {
+        if self.game_over {
+            return Ok(None);
+        }
+
+        if button == event::Button::South {
+            return Ok(self.drop_platform(shared));
+        }
+        Ok(None)
+    }
+}
+
+struct GameOverScene {
+    start_time: Instant,
+}
+
+impl GameOverScene {
+    fn new() -> Self {
+        Self {
+            start_time: Instant::now(),
+        }
+    }
+}
+
+impl Scene for GameOverScene {
+    fn update(&mut self, _ctx: &mut Context, _shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        if self.start_time.elapsed().as_secs() >= 3 {
+            return Ok(Some(SceneTransition::Pop));
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut graphics::Canvas, shared: &SharedState) -> GameResult {
+        canvas.set_screen_coordinates(Rect::new(
+            0.0,
+            0.0,
+            shared.window_width,
+            shared.window_height,
+        ));
+
+        shared.font.draw_text(
+            canvas,
+            "Game Over!",
+            Vec2::new(shared.window_width / 2.0 - 100.0, shared.window_height / 2.0),
+            3.0,
+            Color::RED,
+        );
+
+        Ok(())
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, _input: KeyInput, _shared: &mut SharedState) -> GameResult<Option<SceneTransition>> {
+        // Ignore input during game over state
+        Ok(None)
+    }
+}
+
+/// Thin driver: owns the scene stack and the state shared between scenes,
+/// and forwards ggez events to whichever scene is on top.
+struct GameData {
+    scenes: Vec<Box<dyn Scene>>,
+    shared: SharedState,
+}
+
+impl GameData {
+    fn new(ctx: &Context) -> Self {
+        let window_width = ctx.gfx.window().inner_size().width as f32;
+        let window_height = ctx.gfx.window().inner_size().height as f32;
+
+        let stats_store = make_stats_store();
+        let stats = stats_store.load();
+        let font = BitmapFont::load(ctx).unwrap_or_else(|e| {
+            println!("Failed to load HUD bitmap font: {}", e);
+            BitmapFont::noop(ctx)
+        });
+
+        Self {
+            scenes: vec![Box::new(MenuScene::new())],
+            shared: SharedState {
+                stats,
+                stats_store,
+                config: GameConfig::load(),
+                font,
+                window_width,
+                window_height,
+                last_level: 0,
+            },
+        }
+    }
+
+    fn apply_transition(&mut self, transition: SceneTransition) {
+        match transition {
+            SceneTransition::Push(scene) => self.scenes.push(scene),
+            SceneTransition::Pop => {
+                self.scenes.pop();
             }
-            GameState::Settings => {
-                canvas.set_screen_coordinates(Rect::new(
-                    0.0,
-                    0.0,
-                    self.window_width,
-                    self.window_height,
-                ));
-
-                let text = Text::new("Settings (Press Esc to return)");
-                canvas.draw(
-                    &text,
-                    DrawParam::default()
-                        .dest(Vec2::new(self.window_width / 2.0 - 100.0, self.window_height / 2.0))
-                        .color(Color::WHITE),
-                );
+            SceneTransition::Replace(scene) => {
+                self.scenes.clear();
+                self.scenes.push(scene);
             }
         }
+    }
+
+    fn handle_window_resize(&mut self, ctx: &Context) {
+        self.shared.window_width = ctx.gfx.window().inner_size().width as f32;
+        self.shared.window_height = ctx.gfx.window().inner_size().height as f32;
+    }
+}
+
+impl EventHandler for GameData {
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.update(ctx, &mut self.shared)?,
+            None => None,
+        };
+        if let Some(transition) = transition {
+            self.apply_transition(transition);
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let mut canvas = graphics::Canvas::from_frame(ctx, Color::BLACK);
+
+        for scene in self.scenes.iter_mut() {
+            scene.draw(ctx, &mut canvas, &self.shared)?;
+        }
 
         canvas.finish(ctx)?;
         Ok(())
     }
 
-    fn key_down_event(&mut self, _ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult {
-        match self.state {
-            GameState::Menu => {
-                match input.keycode {
-                    Some(KeyCode::Return) => self.start_game(),
-                    Some(KeyCode::S) => self.state = GameState::Settings,
-                    Some(KeyCode::Q) => std::process::exit(0),
-                    _ => {}
-                }
-            }
-            GameState::Playing => {
-                if let Some(KeyCode::Space) = input.keycode {
-                    if self.check_landing() {
-                        self.add_new_row();
-                        self.level += 1;
-                        self.move_interval *= SPEED_INCREASE;
-                    } else {
-                        // Update high score when game ends
-                        if self.level > self.stats.high_score {
-                            self.stats.high_score = self.level;
-                            if let Err(e) = self.stats.save() {
-                                println!("Failed to save high score: {}", e);
-                            }
-                        }
-                        self.state = GameState::GameOver(Instant::now());
-                    }
-                }
-            }
-            GameState::Settings => {
-                if let Some(KeyCode::Escape) = input.keycode {
-                    self.state = GameState::Menu;
-                }
-            }
-            GameState::GameOver(_) => {} // Ignore input during game over state
+    fn key_down_event(&mut self, ctx: &mut Context, input: KeyInput, _repeat: bool) -> GameResult {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.key_down(ctx, input, &mut self.shared)?,
+            None => None,
+        };
+        if let Some(transition) = transition {
+            self.apply_transition(transition);
+        }
+        Ok(())
+    }
+
+    fn gamepad_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        btn: event::Button,
+        _id: event::GamepadId,
+    ) -> GameResult {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.gamepad_button_down(ctx, btn, &mut self.shared)?,
+            None => None,
+        };
+        if let Some(transition) = transition {
+            self.apply_transition(transition);
+        }
+        Ok(())
+    }
+
+    fn gamepad_axis_event(
+        &mut self,
+        ctx: &mut Context,
+        axis: event::Axis,
+        value: f32,
+        _id: event::GamepadId,
+    ) -> GameResult {
+        let transition = match self.scenes.last_mut() {
+            Some(scene) => scene.gamepad_axis(ctx, axis, value, &mut self.shared)?,
+            None => None,
+        };
+        if let Some(transition) = transition {
+            self.apply_transition(transition);
         }
         Ok(())
     }
@@ -541,4 +1081,3 @@ fn main() -> GameResult {
     let game = GameData::new(&ctx);
     event::run(ctx, event_loop, game)
 }
-